@@ -93,6 +93,8 @@ impl Attribute {
     impl_is_type!(is_none);
     impl_is_type!(is_inherit);
     impl_is_type!(is_current_color);
+    impl_is_type!(is_context_paint);
+    impl_is_type!(is_context_value);
     impl_is_type!(is_aspect_ratio);
     impl_is_type!(is_color);
     impl_is_type!(is_length);
@@ -0,0 +1,299 @@
+// Copyright 2018 Evgeniy Reizner
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use {
+    AttributeId,
+    Color,
+    Length,
+    LengthList,
+    NumberList,
+    Path,
+    Points,
+    Transform,
+    ViewBox,
+    AspectRatio,
+    WriteBuffer,
+    WriteOptions,
+};
+
+use interner::{self, InternedString};
+use write_options::write_num;
+
+/// Value of the SVG attribute.
+#[derive(Clone, PartialEq, Debug)]
+pub enum AttributeValue {
+    /// The `none` keyword.
+    None,
+    /// The `inherit` keyword.
+    Inherit,
+    /// The `currentColor` keyword.
+    CurrentColor,
+    /// The `context-fill` keyword.
+    ///
+    /// Valid only for the `fill` and `stroke` properties. Means "use the paint of the
+    /// element that referenced this one" (e.g. the `<use>` that instantiated a glyph).
+    ContextFill,
+    /// The `context-stroke` keyword.
+    ///
+    /// Same as `ContextFill`, but for the referencing element's `stroke`.
+    ContextStroke,
+    /// The `context-value` keyword.
+    ///
+    /// Valid for plain and list-valued length properties (`stroke-width`,
+    /// `stroke-dasharray`, etc). Means "inherit this value from the referencing element".
+    ContextValue,
+    AspectRatio(AspectRatio),
+    Color(Color),
+    Length(Length),
+    LengthList(LengthList),
+    /// A `FuncIRI`, i.e. `url(#id)`. Only the link id is stored; a paint fallback is not kept.
+    FuncLink(String),
+    /// An `IRI`, i.e. a plain `#id` reference.
+    Link(String),
+    Number(f64),
+    NumberList(NumberList),
+    Path(Path),
+    Points(Points),
+    // Interned: cloning an attribute (e.g. while copying it between nodes, or cloning a
+    // subtree) becomes a refcount bump instead of a heap copy of the string.
+    String(InternedString),
+    Transform(Transform),
+    ViewBox(ViewBox),
+}
+
+impl AttributeValue {
+    /// Returns a default value of the attribute, if it's known.
+    pub fn default_value(id: AttributeId) -> Option<AttributeValue> {
+        match id {
+            AttributeId::Fill => Some(AttributeValue::Color(Color::black())),
+            AttributeId::FillOpacity | AttributeId::StrokeOpacity | AttributeId::Opacity => {
+                Some(AttributeValue::Number(1.0))
+            }
+            AttributeId::Stroke => Some(AttributeValue::None),
+            AttributeId::StrokeWidth => Some(AttributeValue::Number(1.0)),
+            _ => None,
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        *self == AttributeValue::None
+    }
+
+    pub fn is_inherit(&self) -> bool {
+        *self == AttributeValue::Inherit
+    }
+
+    pub fn is_current_color(&self) -> bool {
+        *self == AttributeValue::CurrentColor
+    }
+
+    /// Checks whether the value is the `context-fill` or `context-stroke` keyword.
+    pub fn is_context_paint(&self) -> bool {
+        match *self {
+            AttributeValue::ContextFill | AttributeValue::ContextStroke => true,
+            _ => false,
+        }
+    }
+
+    /// Checks whether the value is the `context-value` keyword.
+    pub fn is_context_value(&self) -> bool {
+        *self == AttributeValue::ContextValue
+    }
+
+    pub fn is_aspect_ratio(&self) -> bool {
+        match *self { AttributeValue::AspectRatio(_) => true, _ => false }
+    }
+
+    pub fn is_color(&self) -> bool {
+        match *self { AttributeValue::Color(_) => true, _ => false }
+    }
+
+    pub fn is_length(&self) -> bool {
+        match *self { AttributeValue::Length(_) => true, _ => false }
+    }
+
+    pub fn is_length_list(&self) -> bool {
+        match *self { AttributeValue::LengthList(_) => true, _ => false }
+    }
+
+    pub fn is_link(&self) -> bool {
+        match *self { AttributeValue::Link(_) => true, _ => false }
+    }
+
+    pub fn is_func_link(&self) -> bool {
+        match *self { AttributeValue::FuncLink(_) => true, _ => false }
+    }
+
+    /// Checks whether the value is a valid `fill`/`stroke` paint value.
+    pub fn is_paint(&self) -> bool {
+        match *self {
+            AttributeValue::None
+            | AttributeValue::Inherit
+            | AttributeValue::CurrentColor
+            | AttributeValue::ContextFill
+            | AttributeValue::ContextStroke
+            | AttributeValue::Color(_)
+            | AttributeValue::FuncLink(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        match *self { AttributeValue::Number(_) => true, _ => false }
+    }
+
+    pub fn is_number_list(&self) -> bool {
+        match *self { AttributeValue::NumberList(_) => true, _ => false }
+    }
+
+    pub fn is_path(&self) -> bool {
+        match *self { AttributeValue::Path(_) => true, _ => false }
+    }
+
+    pub fn is_points(&self) -> bool {
+        match *self { AttributeValue::Points(_) => true, _ => false }
+    }
+
+    pub fn is_string(&self) -> bool {
+        match *self { AttributeValue::String(_) => true, _ => false }
+    }
+
+    pub fn is_transform(&self) -> bool {
+        match *self { AttributeValue::Transform(_) => true, _ => false }
+    }
+
+    pub fn is_viewbox(&self) -> bool {
+        match *self { AttributeValue::ViewBox(_) => true, _ => false }
+    }
+
+    pub fn is_link_container(&self) -> bool {
+        self.is_link() || self.is_func_link()
+    }
+
+    /// Parses a length-list-valued attribute (e.g. `stroke-dasharray`) that may also be
+    /// the standalone `context-value` keyword.
+    ///
+    /// `context-value` has to be checked before we try to parse a number list, since it's
+    /// not itself a valid length list.
+    pub fn parse_context_length_list(text: &str) -> Option<AttributeValue> {
+        let text = text.trim();
+        if text == "context-value" {
+            return Some(AttributeValue::ContextValue);
+        }
+
+        text.parse::<LengthList>().ok().map(AttributeValue::LengthList)
+    }
+
+    /// Parses a paint-valued attribute (`fill`/`stroke`), including the `context-fill`
+    /// and `context-stroke` keywords.
+    pub fn parse_context_paint(text: &str) -> Option<AttributeValue> {
+        match text.trim() {
+            "context-fill" => Some(AttributeValue::ContextFill),
+            "context-stroke" => Some(AttributeValue::ContextStroke),
+            _ => None,
+        }
+    }
+
+    /// Parses a length-valued attribute (e.g. `stroke-width`), including the
+    /// `context-value` keyword.
+    pub fn parse_context_length(text: &str) -> Option<AttributeValue> {
+        let text = text.trim();
+        if text == "context-value" {
+            return Some(AttributeValue::ContextValue);
+        }
+
+        text.parse::<Length>().ok().map(AttributeValue::Length)
+    }
+
+    /// Parses `text` into the `AttributeValue` for attribute `id`, dispatching to
+    /// `parse_context_paint`/`parse_context_length`/`parse_context_length_list` for the
+    /// ids that accept a `context-*` keyword, and falling back to a plain string otherwise.
+    ///
+    /// Without this, those three parse functions had no caller: `fill="context-fill"` on a
+    /// real attribute would only ever become `AttributeValue::String("context-fill")`.
+    pub fn parse(id: AttributeId, text: &str) -> AttributeValue {
+        match id {
+            AttributeId::Fill | AttributeId::Stroke => Self::parse_context_paint(text),
+            AttributeId::StrokeWidth => Self::parse_context_length(text),
+            AttributeId::StrokeDasharray => Self::parse_context_length_list(text),
+            _ => None,
+        }.unwrap_or_else(|| AttributeValue::from(text))
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(s: String) -> Self {
+        AttributeValue::String(interner::intern(&s))
+    }
+}
+
+impl<'a> From<&'a str> for AttributeValue {
+    fn from(s: &'a str) -> Self {
+        AttributeValue::String(interner::intern(s))
+    }
+}
+
+impl WriteBuffer for AttributeValue {
+    fn write_buf_opt(&self, opt: &WriteOptions, buf: &mut Vec<u8>) {
+        match *self {
+            AttributeValue::None => buf.extend_from_slice(b"none"),
+            AttributeValue::Inherit => buf.extend_from_slice(b"inherit"),
+            AttributeValue::CurrentColor => buf.extend_from_slice(b"currentColor"),
+            AttributeValue::ContextFill => buf.extend_from_slice(b"context-fill"),
+            AttributeValue::ContextStroke => buf.extend_from_slice(b"context-stroke"),
+            AttributeValue::ContextValue => buf.extend_from_slice(b"context-value"),
+            AttributeValue::AspectRatio(ref v) => v.write_buf_opt(opt, buf),
+            AttributeValue::Color(ref v) => v.write_buf_opt(opt, buf),
+            AttributeValue::Length(ref v) => v.write_buf_opt(opt, buf),
+            AttributeValue::LengthList(ref v) => v.write_buf_opt(opt, buf),
+            AttributeValue::FuncLink(ref v) => {
+                buf.extend_from_slice(b"url(#");
+                buf.extend_from_slice(v.as_bytes());
+                buf.push(b')');
+            }
+            AttributeValue::Link(ref v) => {
+                buf.push(b'#');
+                buf.extend_from_slice(v.as_bytes());
+            }
+            AttributeValue::Number(v) => write_num(v, opt.numbers_precision, buf),
+            AttributeValue::NumberList(ref v) => v.write_buf_opt(opt, buf),
+            AttributeValue::Path(ref v) => v.write_buf_opt(opt, buf),
+            AttributeValue::Points(ref v) => v.write_buf_opt(opt, buf),
+            AttributeValue::String(ref v) => buf.extend_from_slice(v.as_bytes()),
+            AttributeValue::Transform(ref v) => v.write_buf_opt(opt, buf),
+            AttributeValue::ViewBox(ref v) => v.write_buf_opt(opt, buf),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {AttributeId, AttributeValue};
+
+    #[test]
+    fn context_paint_keywords_parse_for_fill_and_stroke() {
+        assert_eq!(AttributeValue::parse(AttributeId::Fill, "context-fill"), AttributeValue::ContextFill);
+        assert_eq!(AttributeValue::parse(AttributeId::Stroke, "context-stroke"), AttributeValue::ContextStroke);
+
+        // A non-keyword value still falls through to a plain string - full paint parsing
+        // is out of scope here, it just must not be mistaken for a context keyword.
+        assert_eq!(AttributeValue::parse(AttributeId::Fill, "red"), AttributeValue::from("red"));
+    }
+
+    #[test]
+    fn context_value_keyword_parses_for_length_and_length_list_attributes() {
+        assert_eq!(
+            AttributeValue::parse(AttributeId::StrokeWidth, "context-value"),
+            AttributeValue::ContextValue
+        );
+        assert_eq!(
+            AttributeValue::parse(AttributeId::StrokeDasharray, "context-value"),
+            AttributeValue::ContextValue
+        );
+    }
+}
@@ -0,0 +1,71 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A small string interning pool, used to cut down on the allocations otherwise made
+//! for repeated tag names, attribute names, node ids and string attribute values.
+//!
+//! Ruffle hit the same problem in its XML layer and solved it with an interned string
+//! type threaded through the tree; this is the `svgdom` equivalent. Interned strings are
+//! `Rc<str>`, so cloning one (e.g. when copying a subtree or an attribute between nodes)
+//! is a refcount bump rather than a heap copy, while the public API keeps handing out
+//! plain `&str`.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// A cheaply-cloneable, deduplicated string.
+pub type InternedString = Rc<str>;
+
+/// A pool of interned strings.
+///
+/// Looking a string up that's already in the pool returns a clone of the existing
+/// `Rc<str>` (a refcount bump); a new string is allocated once and stays in the pool
+/// until `shrink` drops it once nothing outside the pool references it.
+#[derive(Default)]
+pub struct Interner {
+    strings: RefCell<HashSet<InternedString>>,
+}
+
+impl Interner {
+    /// Creates a new, empty pool.
+    pub fn new() -> Self {
+        Interner { strings: RefCell::new(HashSet::new()) }
+    }
+
+    /// Interns `s`, returning a shared handle to the pooled copy.
+    pub fn intern(&self, s: &str) -> InternedString {
+        if let Some(existing) = self.strings.borrow().get(s) {
+            return existing.clone();
+        }
+
+        let rc: InternedString = Rc::from(s);
+        self.strings.borrow_mut().insert(rc.clone());
+        rc
+    }
+
+    /// Drops pooled strings that are no longer referenced by any node.
+    ///
+    /// Not called automatically - callers can run this periodically (e.g. after a
+    /// bulk removal of nodes) to reclaim memory, since the pool otherwise only grows.
+    pub fn shrink(&self) {
+        self.strings.borrow_mut().retain(|s| Rc::strong_count(s) > 1);
+    }
+}
+
+thread_local! {
+    // A single process-wide pool. There's no per-`Document` handle to thread through
+    // every `AttributeValue`/id construction site, so - same tradeoff as `select.rs`'s
+    // `NTH_CHILD_CACHE` - we share one pool keyed off thread-local storage instead.
+    static POOL: Interner = Interner::new();
+}
+
+/// Interns `s` in the process-wide string pool.
+///
+/// This is what actually backs the "share one allocation per distinct string" claim on
+/// `AttributeValue::String` and `NodeData::id`: both intern through this function rather
+/// than allocating their own `Rc<str>` directly.
+pub fn intern(s: &str) -> InternedString {
+    POOL.with(|pool| pool.intern(s))
+}
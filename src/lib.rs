@@ -77,7 +77,10 @@ doc for details.
 - Attribute values, CDATA with CSS, DOCTYPE, text data and whitespaces will not be preserved.
 - UTF-8 only.
 - Only most popular attributes are parsed, other stored as strings.
-- No compressed SVG (.svgz). You should decompress it by yourself.
+- Compressed SVG (.svgz) is supported on the write side (`WriteOptions::compress`,
+  `Document::write_gz`/`to_vec_gz`) and `decompress_if_needed`/`is_gzip` exist to detect
+  and inflate it on the way in - but no parsing entry point in this build calls them yet,
+  so reading a `.svgz` file still requires decompressing it yourself first.
 - CSS support is minimal.
 - SVG 1.1 Full only (no 2.0 Draft, Basic, Tiny subsets).
 
@@ -109,6 +112,11 @@ mod writer;
 mod attribute_type;
 mod attribute_value;
 mod attributes;
+mod gzip;
+mod interner;
+mod select;
+mod traits;
+mod write_options;
 
 
 pub use crate::attribute::*;
@@ -118,12 +126,19 @@ pub use crate::attributes::*;
 pub use crate::document::Document;
 pub use crate::element_type::ElementType;
 pub use crate::error::*;
+pub use crate::gzip::{decompress_if_needed, is_gzip};
+pub use crate::interner::InternedString;
 pub use crate::name::*;
 pub use crate::names::*;
 pub use crate::node::*;
+pub use crate::select::{Select, Selector, SelectorError};
+pub use crate::traits::{WriteBuffer, WriteToString, WriteToStream};
 pub use crate::tree::iterator::*;
+pub use crate::write_options::{WriteOptions, WriteOptionsPaths, write_num};
 pub use crate::writer::*;
 
+use crate::interner::InternedString as Interned;
+
 pub use svgtypes::{
     Align,
     Angle,
@@ -144,7 +159,7 @@ pub use svgtypes::{
     Points,
     Transform,
     ViewBox,
-    WriteBuffer,
+    WriteBuffer as ValueWriteBuffer,
     WriteOptions as ValueWriteOptions,
 };
 
@@ -183,7 +198,12 @@ pub struct NodeData {
     storage_key: Option<usize>,
     node_type: NodeType,
     tag_name: TagName,
-    id: String,
+    // Interned via `interner::intern` (same pool `AttributeValue::String` uses): `id`s
+    // repeat often (same widget/icon instantiated via `<use>`, the same generated id
+    // scheme, etc), so nodes share one allocation per distinct id instead of each cloning
+    // their own `String`. `text` stays an owned `String` since the white-space
+    // preprocessing in `parser::text` mutates it in place.
+    id: Interned,
     attributes: Attributes,
     linked_nodes: Vec<Node>,
     text: String,
@@ -17,6 +17,8 @@ use {
     WriteOptions,
 };
 
+use interner::{self, InternedString};
+
 /// A trait for SVG id's.
 pub trait SvgId: Copy + PartialEq {
     /// Converts ID into name.
@@ -32,12 +34,17 @@ impl SvgId for ElementId {
 }
 
 /// Qualified name.
+///
+/// The prefix/name strings are interned through `interner::intern` (same pool
+/// `NodeData::id` and `AttributeValue::String` use): tag and attribute names repeat
+/// constantly across a document (every `<rect>`, every custom `data-*` attribute), so
+/// sharing one allocation per distinct name avoids cloning a fresh `String` for each one.
 #[derive(Clone,PartialEq,Debug)]
 pub enum QName<T: SvgId> {
     /// For an SVG name.
-    Id(String, T),
+    Id(InternedString, T),
     /// For an unknown name.
-    Name(String, String),
+    Name(InternedString, InternedString),
 }
 
 impl<T: SvgId> QName<T> {
@@ -52,7 +59,7 @@ impl<T: SvgId> QName<T> {
     /// Checks that this name has specified ID.
     pub fn has_id(&self, prefix: &str, id: T) -> bool {
         match *self {
-            QName::Id(ref prefix2, id2) => id == id2 && prefix == prefix2,
+            QName::Id(ref prefix2, id2) => id == id2 && prefix == &**prefix2,
             _ => false,
         }
     }
@@ -132,8 +139,10 @@ impl<'a, T: SvgId> From<(&'a str, &'a str)> for QNameRef<'a, T> {
 impl<'a, T: SvgId> From<QNameRef<'a, T>> for QName<T> {
     fn from(value: QNameRef<T>) -> Self {
         match value {
-            QNameRef::Id(prefix, id) => QName::Id(prefix.into(), id),
-            QNameRef::Name(prefix, name) => QName::Name(prefix.into(), name.into()),
+            QNameRef::Id(prefix, id) => QName::Id(interner::intern(prefix), id),
+            QNameRef::Name(prefix, name) => {
+                QName::Name(interner::intern(prefix), interner::intern(name))
+            }
         }
     }
 }
@@ -0,0 +1,260 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Converts basic shapes (`rect`, `circle`, `ellipse`, `line`, `polyline`, `polygon`)
+//! into `path` elements, following `usvgr`'s "basic shapes will be converted into paths"
+//! approach. This lets downstream consumers treat all geometry uniformly through the
+//! `d` attribute instead of special-casing each `ElementId`.
+
+use std::fmt::Write;
+
+use {
+    Attribute,
+    AttributeId,
+    AttributeValue,
+    Document,
+    ElementId,
+    Node,
+    NodeType,
+    Path,
+};
+
+/// Rewrites every basic shape element in the document into an equivalent `path` element.
+///
+/// Presentation attributes, `transform` and `id` are carried over as-is; only the
+/// tag name and the geometry-defining attributes change.
+pub fn convert_shapes(dom: &Document) {
+    let nodes: Vec<Node> = dom.root().descendants()
+        .filter(|n| n.node_type() == NodeType::Element)
+        .filter(|n| shape_kind(n).is_some())
+        .collect();
+
+    // Geometry attributes that no longer apply once a shape has become a `path`.
+    const GEOMETRY_ATTRS: &[AttributeId] = &[
+        AttributeId::X, AttributeId::Y, AttributeId::Width, AttributeId::Height,
+        AttributeId::Rx, AttributeId::Ry, AttributeId::Cx, AttributeId::Cy, AttributeId::R,
+        AttributeId::X1, AttributeId::Y1, AttributeId::X2, AttributeId::Y2,
+        AttributeId::Points,
+    ];
+
+    for node in nodes {
+        if let Some(d) = shape_to_path_data(&node) {
+            node.set_tag_name(ElementId::Path);
+
+            node.attributes_mut().retain(|a| {
+                a.id().map_or(true, |id| !GEOMETRY_ATTRS.contains(&id))
+            });
+
+            // Parse the generated path data into an actual `Path`, so the converted
+            // shape gets `AttributeValue::Path` like any other `<path d="...">` would -
+            // not `AttributeValue::String`, which would skip `is_path()`-gated
+            // serialization (compact notation, arc flag joining, etc).
+            let value = match d.parse::<Path>() {
+                Ok(path) => AttributeValue::Path(path),
+                Err(_) => AttributeValue::from(d),
+            };
+            node.set_attribute(Attribute::new(AttributeId::D, value));
+        }
+    }
+}
+
+enum ShapeKind { Rect, Circle, Ellipse, Line, Polyline, Polygon }
+
+fn tag_id(node: &Node) -> Option<ElementId> {
+    match node.tag_name().as_ref() {
+        ::QNameRef::Id(_, id) => Some(id),
+        ::QNameRef::Name(_, _) => None,
+    }
+}
+
+fn shape_kind(node: &Node) -> Option<ShapeKind> {
+    match tag_id(node) {
+        Some(ElementId::Rect) => Some(ShapeKind::Rect),
+        Some(ElementId::Circle) => Some(ShapeKind::Circle),
+        Some(ElementId::Ellipse) => Some(ShapeKind::Ellipse),
+        Some(ElementId::Line) => Some(ShapeKind::Line),
+        Some(ElementId::Polyline) => Some(ShapeKind::Polyline),
+        Some(ElementId::Polygon) => Some(ShapeKind::Polygon),
+        _ => None,
+    }
+}
+
+fn num_attr(node: &Node, id: AttributeId, default: f64) -> f64 {
+    node.attributes().get_value(id)
+        .and_then(|v| if let ::AttributeValue::Number(n) = *v { Some(n) } else { None })
+        .unwrap_or(default)
+}
+
+// The magic constant that makes a cubic Bezier approximate a quarter-circle arc.
+const KAPPA: f64 = 0.552_284_75;
+
+fn shape_to_path_data(node: &Node) -> Option<String> {
+    let kind = shape_kind(node)?;
+    let mut d = String::new();
+
+    match kind {
+        ShapeKind::Rect => {
+            let x = num_attr(node, AttributeId::X, 0.0);
+            let y = num_attr(node, AttributeId::Y, 0.0);
+            let w = num_attr(node, AttributeId::Width, 0.0);
+            let h = num_attr(node, AttributeId::Height, 0.0);
+            if w <= 0.0 || h <= 0.0 {
+                return None;
+            }
+
+            let mut rx = num_attr(node, AttributeId::Rx, 0.0);
+            let mut ry = num_attr(node, AttributeId::Ry, 0.0);
+            if rx <= 0.0 && ry > 0.0 { rx = ry; }
+            if ry <= 0.0 && rx > 0.0 { ry = rx; }
+            rx = rx.min(w / 2.0);
+            ry = ry.min(h / 2.0);
+
+            if rx <= 0.0 || ry <= 0.0 {
+                write!(d, "M {} {} L {} {} L {} {} L {} {} Z",
+                       x, y, x + w, y, x + w, y + h, x, y + h).ok()?;
+            } else {
+                write!(d, "M {} {} ", x + rx, y).ok()?;
+                write!(d, "L {} {} ", x + w - rx, y).ok()?;
+                arc_corner(&mut d, x + w - rx, y, rx, ry, x + w, y + ry);
+                write!(d, "L {} {} ", x + w, y + h - ry).ok()?;
+                arc_corner(&mut d, x + w, y + h - ry, rx, ry, x + w - rx, y + h);
+                write!(d, "L {} {} ", x + rx, y + h).ok()?;
+                arc_corner(&mut d, x + rx, y + h, rx, ry, x, y + h - ry);
+                write!(d, "L {} {} ", x, y + ry).ok()?;
+                arc_corner(&mut d, x, y + ry, rx, ry, x + rx, y);
+                d.push('Z');
+            }
+        }
+        ShapeKind::Circle => {
+            let cx = num_attr(node, AttributeId::Cx, 0.0);
+            let cy = num_attr(node, AttributeId::Cy, 0.0);
+            let r = num_attr(node, AttributeId::R, 0.0);
+            if r <= 0.0 {
+                return None;
+            }
+            ellipse_path(&mut d, cx, cy, r, r);
+        }
+        ShapeKind::Ellipse => {
+            let cx = num_attr(node, AttributeId::Cx, 0.0);
+            let cy = num_attr(node, AttributeId::Cy, 0.0);
+            let rx = num_attr(node, AttributeId::Rx, 0.0);
+            let ry = num_attr(node, AttributeId::Ry, 0.0);
+            if rx <= 0.0 || ry <= 0.0 {
+                return None;
+            }
+            ellipse_path(&mut d, cx, cy, rx, ry);
+        }
+        ShapeKind::Line => {
+            let x1 = num_attr(node, AttributeId::X1, 0.0);
+            let y1 = num_attr(node, AttributeId::Y1, 0.0);
+            let x2 = num_attr(node, AttributeId::X2, 0.0);
+            let y2 = num_attr(node, AttributeId::Y2, 0.0);
+            write!(d, "M {} {} L {} {}", x1, y1, x2, y2).ok()?;
+        }
+        ShapeKind::Polyline | ShapeKind::Polygon => {
+            let points = match node.attributes().get_value(AttributeId::Points) {
+                Some(&::AttributeValue::Points(ref p)) => p.clone(),
+                _ => return None,
+            };
+
+            let mut iter = points.iter();
+            let (x0, y0) = iter.next()?;
+            write!(d, "M {} {}", x0, y0).ok()?;
+            for (x, y) in iter {
+                write!(d, " L {} {}", x, y).ok()?;
+            }
+
+            if let ShapeKind::Polygon = kind {
+                d.push('Z');
+            }
+        }
+    }
+
+    Some(d)
+}
+
+// Emits a cubic Bezier that approximates a quarter-circle/ellipse corner arc from
+// (x0, y0) to (x1, y1), with radii (rx, ry).
+fn arc_corner(d: &mut String, x0: f64, y0: f64, rx: f64, ry: f64, x1: f64, y1: f64) {
+    let sx = (x1 - x0).signum();
+    let sy = (y1 - y0).signum();
+
+    // The rect outline is walked clockwise, so a corner that moves the same way in x and
+    // y (top-right, bottom-left) leaves horizontally and arrives vertically, and the other
+    // two leave vertically and arrive horizontally.
+    let ((c1x, c1y), (c2x, c2y)) = if sx == sy {
+        ((x0 + sx * rx * KAPPA, y0), (x1, y1 - sy * ry * KAPPA))
+    } else {
+        ((x0, y0 + sy * ry * KAPPA), (x1 - sx * rx * KAPPA, y1))
+    };
+
+    let _ = write!(d, "C {} {} {} {} {} {} ", c1x, c1y, c2x, c2y, x1, y1);
+}
+
+fn ellipse_path(d: &mut String, cx: f64, cy: f64, rx: f64, ry: f64) {
+    let kx = rx * KAPPA;
+    let ky = ry * KAPPA;
+
+    // One continuous subpath, one quarter-ellipse cubic per corner, closed once at the end.
+    let _ = write!(
+        d,
+        "M {} {} C {} {} {} {} {} {} C {} {} {} {} {} {} \
+         C {} {} {} {} {} {} C {} {} {} {} {} {} Z",
+        cx - rx, cy,
+        cx - rx, cy + ky, cx - kx, cy + ry, cx, cy + ry,
+        cx + kx, cy + ry, cx + rx, cy + ky, cx + rx, cy,
+        cx + rx, cy - ky, cx + kx, cy - ry, cx, cy - ry,
+        cx - kx, cy - ry, cx - rx, cy - ky, cx - rx, cy,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use {AttributeId, AttributeValue, Document, ElementId, Path};
+    use super::tag_id;
+
+    #[test]
+    fn converted_circle_is_a_single_closed_four_segment_path() {
+        let doc = Document::from_str("<svg><circle cx=\"5\" cy=\"5\" r=\"5\"/></svg>").unwrap();
+
+        super::convert_shapes(&doc);
+
+        let path_node = doc.root().descendants()
+            .find(|n| tag_id(n) == Some(ElementId::Path))
+            .unwrap();
+
+        let value = path_node.attributes().get_value(AttributeId::D).unwrap();
+        // Must be a real `AttributeValue::Path`, not `AttributeValue::String` - otherwise
+        // it would fail `is_path()` and skip path-specific serialization.
+        let d = match *value {
+            AttributeValue::Path(ref p) => p,
+            _ => panic!("expected AttributeValue::Path, got {:?}", value),
+        };
+
+        // Parse the written `d` back to make sure it's one well-formed subpath: a single
+        // `M`, exactly 4 quarter-ellipse `C`s (one per corner), and a single trailing `Z`
+        // - not the two-`Z`, missing-`M` garbage the previous version emitted.
+        let reparsed: Path = d.to_string().parse().unwrap();
+        let segs: Vec<::PathSegment> = reparsed.iter().collect();
+        assert_eq!(segs.len(), 6, "expected M + 4×C + Z, got {:?}", segs);
+
+        // The ellipse starts and ends at (cx - rx, cy) = (0, 5).
+        match segs[0] {
+            ::PathSegment::MoveTo { x, y, .. } => {
+                assert!((x - 0.0).abs() < 1e-9 && (y - 5.0).abs() < 1e-9);
+            }
+            ref other => panic!("expected MoveTo as first segment, got {:?}", other),
+        }
+        match segs[4] {
+            ::PathSegment::CurveTo { x, y, .. } => {
+                assert!((x - 0.0).abs() < 1e-9 && (y - 5.0).abs() < 1e-9);
+            }
+            ref other => panic!("expected CurveTo as the last curve segment, got {:?}", other),
+        }
+        match segs[5] {
+            ::PathSegment::ClosePath { .. } => {}
+            ref other => panic!("expected ClosePath as the last segment, got {:?}", other),
+        }
+    }
+}
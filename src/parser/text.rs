@@ -2,22 +2,67 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::cell::RefCell;
+
 use {
-    Attribute,
     AttributeId,
     AttributeValue,
     Document,
-    Name,
     Node,
     NodeType,
 };
 
-#[derive(Clone,Copy,PartialEq)]
-enum XmlSpace {
-    Default,
-    Preserve,
+// The resolved white-space handling mode for a node, combining the legacy `xml:space`
+// attribute (only `Normal`/`Pre`) with the CSS 2/SVG 2 `white-space` property.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum WhiteSpace {
+    /// `white-space: normal` / `xml:space="default"`.
+    Normal,
+    /// `white-space: pre` / `xml:space="preserve"`.
+    Pre,
+    /// `white-space: pre-wrap`. Whitespace-processing-wise, same as `Pre`.
+    PreWrap,
+    /// `white-space: pre-line`. Collapses spaces/tabs, like `Normal`, but keeps newlines.
+    PreLine,
+    /// `white-space: nowrap`. Whitespace-processing-wise, same as `Normal`.
+    NoWrap,
+}
+
+impl WhiteSpace {
+    // Whether this mode collapses/trims runs of spaces at node boundaries, the way
+    // `Normal` always has.
+    fn collapses_boundary_spaces(self) -> bool {
+        match self {
+            WhiteSpace::Pre | WhiteSpace::PreWrap => false,
+            WhiteSpace::Normal | WhiteSpace::PreLine | WhiteSpace::NoWrap => true,
+        }
+    }
+
+    fn from_xmlspace_str(s: &str) -> Option<WhiteSpace> {
+        match s {
+            "preserve" => Some(WhiteSpace::Pre),
+            "default" => Some(WhiteSpace::Normal),
+            _ => None,
+        }
+    }
+
+    fn from_css_str(s: &str) -> Option<WhiteSpace> {
+        match s {
+            "normal" => Some(WhiteSpace::Normal),
+            "pre" => Some(WhiteSpace::Pre),
+            "pre-wrap" => Some(WhiteSpace::PreWrap),
+            "pre-line" => Some(WhiteSpace::PreLine),
+            "nowrap" => Some(WhiteSpace::NoWrap),
+            _ => None,
+        }
+    }
 }
 
+// Backward-compatible alias used by the rest of this module; kept so the bulk of the
+// pre-existing code below (which predates CSS `white-space` support) doesn't need to
+// be reshuffled.
+use self::WhiteSpace as XmlSpace;
+
 trait StrTrim {
     fn remove_first(&mut self);
     fn remove_last(&mut self);
@@ -45,19 +90,29 @@ impl StrTrim for String {
     }
 }
 
+thread_local! {
+    // Caches each element's resolved `WhiteSpace` for the duration of one `prepare_text`
+    // pass. `prepare_text_children` re-resolves the same node's white-space mode (once per
+    // `_prepare_text` visit, then again per descendant text node that shares it), so this
+    // avoids re-scanning `style`/`xml:space` repeatedly. Unlike the single prior revision
+    // of this cache, which round-tripped the resolved value through an invisible
+    // `xml:space="default"/"preserve"` attribute, this keeps the full 5-state `WhiteSpace`
+    // - storing it as `xml:space` would silently collapse `PreLine`/`NoWrap` down to the
+    // 2-state legacy values on the very next lookup. Keyed by `Node` identity, same
+    // approach as `select.rs`'s `NTH_CHILD_CACHE`.
+    static XMLSPACE_CACHE: RefCell<Vec<(Node, XmlSpace)>> = RefCell::new(Vec::new());
+}
+
 // Prepare text nodes according to the spec: https://www.w3.org/TR/SVG11/text.html#WhiteSpace
 //
 // This function handles:
-// - 'xml:space' processing
+// - the CSS 2 / SVG 2 'white-space' property, falling back to the legacy 'xml:space'
 // - tabs and newlines removing/replacing
 // - spaces trimming
 pub fn prepare_text(dom: &Document) {
-    _prepare_text(&dom.root(), XmlSpace::Default);
+    XMLSPACE_CACHE.with(|cache| cache.borrow_mut().clear());
 
-    // Remove invisible 'xml:space' attributes created during text processing.
-    for node in dom.descendants().filter(|n| n.node_type() == NodeType::Element) {
-        node.attributes_mut().retain(|attr| attr.visible == true);
-    }
+    _prepare_text(&dom.root(), XmlSpace::Normal);
 }
 
 fn _prepare_text(parent: &Node, parent_xmlspace: XmlSpace) {
@@ -77,38 +132,40 @@ fn _prepare_text(parent: &Node, parent_xmlspace: XmlSpace) {
     }
 }
 
+// Resolves the effective white-space mode of `node`: the CSS `white-space` property (read
+// from `style`) wins if present, `xml:space` is the fallback, and `default` is inherited
+// from the parent when neither is set. Cached per node for the current `prepare_text` pass
+// - see `XMLSPACE_CACHE`.
 fn get_xmlspace(node: &Node, default: XmlSpace) -> XmlSpace {
-    {
-        let attrs = node.attributes();
-        let v = attrs.get_value(AttributeId::XmlSpace);
-        if let Some(&AttributeValue::String(ref s)) = v {
-            if s == "preserve" {
-                return XmlSpace::Preserve;
-            } else {
-                return XmlSpace::Default;
-            }
-        }
+    let cached = XMLSPACE_CACHE.with(|cache| {
+        cache.borrow().iter().find(|&&(ref n, _)| n == node).map(|&(_, ws)| ws)
+    });
+    if let Some(ws) = cached {
+        return ws;
     }
 
-    // 'xml:space' is not set - set it manually.
-    set_xmlspace(node, default);
+    let resolved = effective_xmlspace(node, default);
 
-    default
-}
+    XMLSPACE_CACHE.with(|cache| cache.borrow_mut().push((node.clone(), resolved)));
 
-fn set_xmlspace(node: &Node, xmlspace: XmlSpace) {
-    let xmlspace_str = match xmlspace {
-        XmlSpace::Default => "default",
-        XmlSpace::Preserve => "preserve",
-    };
+    resolved
+}
 
-    let attr = Attribute {
-        name: Name::Id(AttributeId::XmlSpace),
-        value: AttributeValue::String(xmlspace_str.to_owned()),
-        visible: false,
-    };
+// A very small `style="...; white-space: <value>; ..."` scanner - this crate's CSS
+// support is intentionally minimal, so we don't pull in a full declaration-block parser
+// just for a single property.
+fn white_space_from_style(style: &str) -> Option<WhiteSpace> {
+    for decl in style.split(';') {
+        let mut parts = decl.splitn(2, ':');
+        let name = parts.next()?.trim();
+        let value = parts.next()?.trim();
+
+        if name == "white-space" {
+            return WhiteSpace::from_css_str(value);
+        }
+    }
 
-    node.set_attribute(attr);
+    None
 }
 
 fn prepare_text_children(parent: &Node, xmlspace: XmlSpace) {
@@ -135,7 +192,7 @@ fn prepare_text_children(parent: &Node, xmlspace: XmlSpace) {
         let node = &nodes[0];
 
         // Do nothing when xml:space=preserve.
-        if xmlspace == XmlSpace::Default {
+        if xmlspace.collapses_boundary_spaces() {
             let mut text = node.text_mut();
 
             match text.len() {
@@ -195,7 +252,7 @@ fn prepare_text_children(parent: &Node, xmlspace: XmlSpace) {
             // Remove space from the second text node if both nodes has bound spaces.
             // From: '<text>Text <tspan> text</tspan></text>'
             // To:   '<text>Text <tspan>text</tspan></text>'
-            if xmlspace1 == XmlSpace::Default && xmlspace2 == XmlSpace::Default {
+            if xmlspace1.collapses_boundary_spaces() && xmlspace2.collapses_boundary_spaces() {
                 if c2 == Some(b' ') && c2 == c3 {
                     text2.remove_first();
                 }
@@ -204,11 +261,11 @@ fn prepare_text_children(parent: &Node, xmlspace: XmlSpace) {
             let is_first = i == 0;
             let is_last  = i == len - 1;
 
-            if is_first && c1 == Some(b' ') && xmlspace1 == XmlSpace::Default {
+            if is_first && c1 == Some(b' ') && xmlspace1.collapses_boundary_spaces() {
                 // Remove leading space of the first text node.
                 text1.remove_first();
             } else if    is_last && c4 == Some(b' ') && !text2.is_empty()
-                      && xmlspace2 == XmlSpace::Default {
+                      && xmlspace2.collapses_boundary_spaces() {
                 // Remove trailing space of the last text node.
                 // Also check that 'text2' is not empty already.
                 text2.remove_last();
@@ -219,6 +276,115 @@ fn prepare_text_children(parent: &Node, xmlspace: XmlSpace) {
     }
 }
 
+// The actual `style`/`xml:space` resolution logic, shared by `get_xmlspace` (which adds
+// `prepare_text`-local caching on top) and `Node::text_content()` (which calls this
+// directly, uncached, since it must not mutate anything and isn't part of a single
+// amortized tree walk).
+fn effective_xmlspace(node: &Node, inherited: XmlSpace) -> XmlSpace {
+    let attrs = node.attributes();
+
+    if let Some(&AttributeValue::String(ref s)) = attrs.get_value(AttributeId::Style) {
+        if let Some(ws) = white_space_from_style(s) {
+            return ws;
+        }
+    }
+
+    match attrs.get_value(AttributeId::XmlSpace) {
+        Some(&AttributeValue::String(ref s)) => {
+            WhiteSpace::from_xmlspace_str(s).unwrap_or(inherited)
+        }
+        _ => inherited,
+    }
+}
+
+fn collect_text_nodes(parent: &Node, inherited: XmlSpace, out: &mut Vec<(Node, XmlSpace)>) {
+    let xmlspace = effective_xmlspace(parent, inherited);
+
+    for child in parent.children() {
+        match child.node_type() {
+            NodeType::Text => out.push((child.clone(), xmlspace)),
+            NodeType::Element => collect_text_nodes(&child, xmlspace, out),
+            _ => {}
+        }
+    }
+}
+
+impl Node {
+    /// Returns the concatenated, white-space-processed visible text of a `text`/`tspan`
+    /// subtree, the way a renderer would lay it out.
+    ///
+    /// This applies the same `xml:space` collapsing and cross-node space deduplication
+    /// as `prepare_text`, but non-destructively: the DOM is left untouched, so callers
+    /// can use this for search, accessibility labels or text measurement without having
+    /// to serialize and re-parse the document.
+    pub fn text_content(&self) -> String {
+        let mut nodes = Vec::new();
+        collect_text_nodes(self, XmlSpace::Normal, &mut nodes);
+
+        let mut texts: Vec<String> = nodes.iter()
+            .map(|&(ref n, xs)| {
+                let mut s = n.text_mut().clone();
+                trim_text(&mut s, xs);
+                s
+            })
+            .collect();
+
+        // 'trim_text' already collapsed all spaces into a single one, so we only have
+        // to check for one leading or trailing space, same as 'prepare_text_children'.
+        if texts.len() == 1 {
+            if nodes[0].1.collapses_boundary_spaces() {
+                match texts[0].len() {
+                    0 => {}
+                    1 => if texts[0].as_bytes()[0] == b' ' { texts[0].clear(); }
+                    _ => {
+                        if texts[0].as_bytes()[0] == b' ' {
+                            texts[0].remove(0);
+                        }
+
+                        let last = texts[0].len() - 1;
+                        if texts[0].as_bytes()[last] == b' ' {
+                            texts[0].pop();
+                        }
+                    }
+                }
+            }
+        } else if texts.len() > 1 {
+            let len = texts.len() - 1;
+            for i in 0..len {
+                let xmlspace1 = nodes[i].1;
+                let xmlspace2 = nodes[i + 1].1;
+
+                let (left, right) = texts.split_at_mut(i + 1);
+                let text1 = &mut left[i];
+                let text2 = &mut right[0];
+
+                let c1 = text1.as_bytes().first().cloned();
+                let c2 = text1.as_bytes().last().cloned();
+                let c3 = text2.as_bytes().first().cloned();
+                let c4 = text2.as_bytes().last().cloned();
+
+                if xmlspace1.collapses_boundary_spaces() && xmlspace2.collapses_boundary_spaces() {
+                    if c2 == Some(b' ') && c2 == c3 {
+                        text2.remove(0);
+                    }
+                }
+
+                let is_first = i == 0;
+                let is_last = i == len - 1;
+
+                if is_first && c1 == Some(b' ') && xmlspace1.collapses_boundary_spaces() {
+                    text1.remove(0);
+                } else if is_last && c4 == Some(b' ') && !text2.is_empty()
+                          && xmlspace2.collapses_boundary_spaces() {
+                    text2.pop();
+                }
+            }
+        }
+
+        texts.concat()
+    }
+}
+
 fn trim_text(text: &mut String, xmlspace: XmlSpace) {
     // In place map() alternative.
     fn replace_if<P>(data: &mut Vec<u8>, p: P, new: u8)
@@ -236,7 +402,8 @@ fn trim_text(text: &mut String, xmlspace: XmlSpace) {
 
     // Process whitespaces as described in: https://www.w3.org/TR/SVG11/text.html#WhiteSpace
     match xmlspace {
-        XmlSpace::Default => {
+        // `normal`/`nowrap`: behave exactly like the legacy `xml:space="default"`.
+        XmlSpace::Normal | XmlSpace::NoWrap => {
             // 'First, it will remove all newline characters.'
             bytes.retain(|c| *c != b'\n' && *c != b'\r');
 
@@ -259,7 +426,8 @@ fn trim_text(text: &mut String, xmlspace: XmlSpace) {
                 }
             }
         }
-        XmlSpace::Preserve => {
+        // `pre`/`pre-wrap`: behave exactly like the legacy `xml:space="preserve"`.
+        XmlSpace::Pre | XmlSpace::PreWrap => {
             // 'It will convert all newline and tab characters into space characters.'
 
             // '\r\n' should be converted into a single space.
@@ -277,5 +445,44 @@ fn trim_text(text: &mut String, xmlspace: XmlSpace) {
 
             replace_if(&mut bytes, |c| c == b'\t' || c == b'\n' || c == b'\r', b' ');
         }
+        // `pre-line`: collapse spaces/tabs like `Normal`, but keep newlines as line breaks
+        // instead of stripping them.
+        XmlSpace::PreLine => {
+            replace_if(&mut bytes, |c| c == b'\t', b' ');
+
+            if bytes.len() > 1 {
+                let mut pos = 0;
+                while pos < bytes.len() - 1 {
+                    if bytes[pos] == b' ' && bytes[pos + 1] == b' ' {
+                        bytes.remove(pos);
+                    } else {
+                        pos += 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Document, NodeType};
+
+    #[test]
+    fn pre_line_inherits_through_element_with_no_local_override() {
+        let doc = Document::from_str(
+            "<svg style=\"white-space:pre-line\"><text>Line1\nLine2</text></svg>"
+        ).unwrap();
+
+        super::prepare_text(&doc);
+
+        let text = doc.root().descendants()
+            .find(|n| n.node_type() == NodeType::Text)
+            .unwrap();
+
+        // Inherited `pre-line` must survive both `get_xmlspace` lookups done on this
+        // node's parent (once in `_prepare_text`, once in `prepare_text_children`), so
+        // the newline between "Line1" and "Line2" is preserved rather than stripped.
+        assert_eq!(&*text.text_mut(), "Line1\nLine2");
     }
 }
\ No newline at end of file
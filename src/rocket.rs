@@ -6,11 +6,18 @@ use self::rocket::response::{self, Responder, Response};
 use super::document::Document;
 use std::io::Cursor;
 
+use WriteToStream;
+
 #[cfg(feature = "rocket-support")]
 impl<'r> Responder<'r> for Document {
     fn respond_to(self, _: &Request) -> response::Result<'r> {
+        // Serialize the whole document into an in-memory buffer via `write_to` and send it
+        // as a sized body; the document is not streamed into the response.
+        let mut body = Vec::new();
+        self.write_to(&mut body).map_err(|_| rocket::http::Status::InternalServerError)?;
+
         Response::build()
-            .sized_body(Cursor::new(self.to_string()))
+            .sized_body(Cursor::new(body))
             .header(ContentType::new("image", "svg+xml"))
             .ok()
     }
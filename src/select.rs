@@ -0,0 +1,707 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A minimal CSS selector engine for querying a `Document`.
+//!
+//! Only the subset of CSS selectors that are useful for SVG documents is supported:
+//! type, `*`, `#id`, `.class`, `[attr]`/`[attr=val]`/`[attr~=val]` attribute selectors,
+//! the descendant/child/adjacent-sibling/general-sibling combinators and the structural
+//! pseudo-classes `:first-child`, `:last-child` and `:nth-child(an+b)`.
+//!
+//! Selectors are matched right-to-left: we first check whether a candidate node matches
+//! the rightmost compound selector and then walk leftward, satisfying each combinator
+//! against ancestors/siblings as we go. This is the same strategy used by browser engines,
+//! since it lets us reject the vast majority of nodes after a single, cheap check.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use {
+    AttributeId,
+    Document,
+    Node,
+    NodeType,
+};
+
+/// An error that can occur while parsing a selector string.
+#[derive(Clone, PartialEq, Debug)]
+pub enum SelectorError {
+    /// The selector string is empty.
+    EmptySelector,
+    /// The selector string contains invalid syntax.
+    InvalidSelector(String),
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SelectorError::EmptySelector => write!(f, "selector is empty"),
+            SelectorError::InvalidSelector(ref s) => write!(f, "invalid selector: '{}'", s),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum AttrMatch {
+    Exists,
+    Equals(String),
+    // `~=`: value is one of a whitespace separated list.
+    Includes(String),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum NthChild {
+    // `a`, `b` in `an + b`.
+    AnB(i32, i32),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct CompoundSelector {
+    tag_name: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attrs: Vec<(String, AttrMatch)>,
+    first_child: bool,
+    last_child: bool,
+    nth_child: Option<NthChild>,
+}
+
+impl CompoundSelector {
+    fn new() -> Self {
+        CompoundSelector {
+            tag_name: None,
+            id: None,
+            classes: Vec::new(),
+            attrs: Vec::new(),
+            first_child: false,
+            last_child: false,
+            nth_child: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Combinator {
+    // ` `
+    Descendant,
+    // `>`
+    Child,
+    // `+`
+    AdjacentSibling,
+    // `~`
+    GeneralSibling,
+}
+
+// A single compound selector and a combinator. Once `parse_complex_selector` has reversed
+// and shifted the list, `steps[i].combinator` (for `i >= 1`) is the combinator between
+// `steps[i - 1]` and `steps[i]`; `steps[0]`'s combinator is not used.
+#[derive(Clone, PartialEq, Debug)]
+struct Step {
+    combinator: Option<Combinator>,
+    selector: CompoundSelector,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct ComplexSelector {
+    // Stored right-to-left, i.e. `steps[0]` is the rightmost (key) compound selector.
+    steps: Vec<Step>,
+}
+
+/// A parsed selector list (a comma-separated list of selectors).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Selector {
+    list: Vec<ComplexSelector>,
+}
+
+impl Selector {
+    /// Parses a selector string.
+    pub fn parse(text: &str) -> Result<Selector, SelectorError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(SelectorError::EmptySelector);
+        }
+
+        let mut list = Vec::new();
+        for part in text.split(',') {
+            list.push(parse_complex_selector(part.trim())?);
+        }
+
+        Ok(Selector { list })
+    }
+
+    /// Checks if the node matches this selector.
+    pub fn matches(&self, node: &Node) -> bool {
+        self.list.iter().any(|sel| matches_complex(sel, node))
+    }
+}
+
+fn parse_complex_selector(text: &str) -> Result<ComplexSelector, SelectorError> {
+    if text.is_empty() {
+        return Err(SelectorError::InvalidSelector(text.to_owned()));
+    }
+
+    // Split on combinators while keeping them, then parse each compound selector.
+    // We build the list left-to-right first and reverse it at the end, since matching
+    // is easier to reason about (and implement) right-to-left.
+    let mut steps = Vec::new();
+    let mut combinator = None;
+    let mut buf = String::new();
+
+    let flush = |buf: &mut String,
+                 combinator: Option<Combinator>,
+                 steps: &mut Vec<Step>| -> Result<(), SelectorError> {
+        let s = buf.trim();
+        if s.is_empty() {
+            return Err(SelectorError::InvalidSelector(s.to_owned()));
+        }
+
+        steps.push(Step { combinator, selector: parse_compound_selector(s)? });
+        buf.clear();
+        Ok(())
+    };
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '>' | '+' | '~' => {
+                flush(&mut buf, combinator, &mut steps)?;
+                combinator = Some(match c {
+                    '>' => Combinator::Child,
+                    '+' => Combinator::AdjacentSibling,
+                    _ => Combinator::GeneralSibling,
+                });
+            }
+            ' ' | '\t' | '\n' => {
+                // A run of whitespace is either pure formatting around an explicit
+                // combinator, or itself the descendant combinator.
+                if !buf.trim().is_empty() {
+                    // Peek ahead: if the next non-space char is an explicit combinator,
+                    // let that branch above handle it.
+                    let mut is_explicit = false;
+                    let mut lookahead = chars.clone();
+                    while let Some(&c2) = lookahead.peek() {
+                        if c2 == ' ' || c2 == '\t' || c2 == '\n' {
+                            lookahead.next();
+                        } else {
+                            is_explicit = c2 == '>' || c2 == '+' || c2 == '~';
+                            break;
+                        }
+                    }
+
+                    if !is_explicit {
+                        flush(&mut buf, combinator, &mut steps)?;
+                        combinator = Some(Combinator::Descendant);
+                    }
+                }
+            }
+            _ => buf.push(c),
+        }
+    }
+    flush(&mut buf, combinator, &mut steps)?;
+
+    // Reverse so `steps[0]` is the rightmost (key) compound selector.
+    steps.reverse();
+
+    // After reversing, each step's combinator describes how it links to the step that
+    // is now *after* it (i.e. to its left in the original text), so every combinator
+    // has to shift by one position. Snapshot the pre-shift values first: writing
+    // directly into `steps` while reading from it would cascade the last-seen
+    // combinator onto every earlier step instead of shifting each one individually.
+    let original_combinators: Vec<Option<Combinator>> =
+        steps.iter().map(|s| s.combinator).collect();
+    for i in 1..steps.len() {
+        steps[i].combinator = original_combinators[i - 1];
+    }
+    // `steps[0]`'s own combinator is never read (`matches_complex` only checks its
+    // selector, and `match_rest` starts at index 1), so it's left as whatever it was
+    // before the shift.
+
+    Ok(ComplexSelector { steps })
+}
+
+fn parse_compound_selector(text: &str) -> Result<CompoundSelector, SelectorError> {
+    let mut sel = CompoundSelector::new();
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    // Optional leading type selector / universal selector.
+    if i < bytes.len() && bytes[i] != b'#' && bytes[i] != b'.' && bytes[i] != b'[' && bytes[i] != b':' {
+        let start = i;
+        while i < bytes.len() && !matches!(bytes[i], b'#' | b'.' | b'[' | b':') {
+            i += 1;
+        }
+        let name = &text[start..i];
+        if name != "*" {
+            sel.tag_name = Some(name.to_owned());
+        }
+    }
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => {
+                i += 1;
+                let start = i;
+                while i < bytes.len() && !matches!(bytes[i], b'#' | b'.' | b'[' | b':') {
+                    i += 1;
+                }
+                sel.id = Some(text[start..i].to_owned());
+            }
+            b'.' => {
+                i += 1;
+                let start = i;
+                while i < bytes.len() && !matches!(bytes[i], b'#' | b'.' | b'[' | b':') {
+                    i += 1;
+                }
+                sel.classes.push(text[start..i].to_owned());
+            }
+            b'[' => {
+                let end = text[i..].find(']')
+                    .map(|p| i + p)
+                    .ok_or_else(|| SelectorError::InvalidSelector(text.to_owned()))?;
+                let inner = &text[i + 1..end];
+                if let Some(pos) = inner.find("~=") {
+                    sel.attrs.push((
+                        inner[..pos].trim().to_owned(),
+                        AttrMatch::Includes(unquote(inner[pos + 2..].trim())),
+                    ));
+                } else if let Some(pos) = inner.find('=') {
+                    sel.attrs.push((
+                        inner[..pos].trim().to_owned(),
+                        AttrMatch::Equals(unquote(inner[pos + 1..].trim())),
+                    ));
+                } else {
+                    sel.attrs.push((inner.trim().to_owned(), AttrMatch::Exists));
+                }
+                i = end + 1;
+            }
+            b':' => {
+                i += 1;
+                let start = i;
+                while i < bytes.len() && bytes[i] != b'(' && !matches!(bytes[i], b'#' | b'.' | b'[' | b':') {
+                    i += 1;
+                }
+                let name = &text[start..i];
+
+                if i < bytes.len() && bytes[i] == b'(' {
+                    let end = text[i..].find(')')
+                        .map(|p| i + p)
+                        .ok_or_else(|| SelectorError::InvalidSelector(text.to_owned()))?;
+                    let arg = text[i + 1..end].trim();
+                    match name {
+                        "nth-child" => sel.nth_child = Some(parse_nth(arg)?),
+                        _ => return Err(SelectorError::InvalidSelector(text.to_owned())),
+                    }
+                    i = end + 1;
+                } else {
+                    match name {
+                        "first-child" => sel.first_child = true,
+                        "last-child" => sel.last_child = true,
+                        _ => return Err(SelectorError::InvalidSelector(text.to_owned())),
+                    }
+                }
+            }
+            _ => return Err(SelectorError::InvalidSelector(text.to_owned())),
+        }
+    }
+
+    Ok(sel)
+}
+
+fn unquote(s: &str) -> String {
+    let s = s.trim();
+    if s.len() >= 2 {
+        let first = s.as_bytes()[0];
+        let last = s.as_bytes()[s.len() - 1];
+        if (first == b'"' && last == b'"') || (first == b'\'' && last == b'\'') {
+            return s[1..s.len() - 1].to_owned();
+        }
+    }
+    s.to_owned()
+}
+
+fn parse_nth(arg: &str) -> Result<NthChild, SelectorError> {
+    let arg = arg.trim().to_lowercase();
+
+    if arg == "odd" {
+        return Ok(NthChild::AnB(2, 1));
+    }
+    if arg == "even" {
+        return Ok(NthChild::AnB(2, 0));
+    }
+
+    if let Some(pos) = arg.find('n') {
+        let a_part = arg[..pos].trim();
+        let a = match a_part {
+            "" | "+" => 1,
+            "-" => -1,
+            _ => a_part.parse::<i32>()
+                .map_err(|_| SelectorError::InvalidSelector(arg.clone()))?,
+        };
+
+        let rest = arg[pos + 1..].trim();
+        let b = if rest.is_empty() {
+            0
+        } else {
+            let rest = rest.replace(' ', "");
+            rest.parse::<i32>().map_err(|_| SelectorError::InvalidSelector(arg.clone()))?
+        };
+
+        Ok(NthChild::AnB(a, b))
+    } else {
+        let b = arg.parse::<i32>().map_err(|_| SelectorError::InvalidSelector(arg.clone()))?;
+        Ok(NthChild::AnB(0, b))
+    }
+}
+
+// A fixed-size ancestor bloom filter, rebuilt incrementally while descending the tree.
+//
+// Each ancestor contributes its tag name, id and every class to the filter. Since a
+// bloom filter never yields false negatives, we can use it to cheaply reject a
+// descendant/child chain when a required ancestor feature is provably absent, without
+// ever rejecting a chain that could actually match.
+const BLOOM_BITS: usize = 256;
+
+struct BloomFilter {
+    bits: [u64; BLOOM_BITS / 64],
+}
+
+impl BloomFilter {
+    fn new() -> Self {
+        BloomFilter { bits: [0; BLOOM_BITS / 64] }
+    }
+
+    fn insert(&mut self, s: &str) {
+        let h = hash_str(s);
+        let idx = (h as usize) % BLOOM_BITS;
+        self.bits[idx / 64] |= 1 << (idx % 64);
+    }
+
+    fn might_contain(&self, s: &str) -> bool {
+        let h = hash_str(s);
+        let idx = (h as usize) % BLOOM_BITS;
+        self.bits[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    fn push_node(&mut self, node: &Node) {
+        self.insert(&node.tag_name_string());
+        if let Some(id) = node.id_string() {
+            self.insert(&format!("#{}", id));
+        }
+        for class in node.class_list() {
+            self.insert(&format!(".{}", class));
+        }
+    }
+}
+
+// Helpers that translate selector concepts (tag name, id, classes, arbitrary
+// attributes) onto the node/attribute API used elsewhere in the crate.
+impl Node {
+    fn tag_name_string(&self) -> String {
+        self.tag_name().to_string()
+    }
+
+    fn id_string(&self) -> Option<String> {
+        let id = self.id();
+        if id.is_empty() { None } else { Some(id.clone()) }
+    }
+
+    fn class_list(&self) -> Vec<String> {
+        match self.attributes().get_value(AttributeId::Class) {
+            Some(&::AttributeValue::String(ref s)) => {
+                s.split_whitespace().map(|s| s.to_owned()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn attribute_string(&self, name: &str) -> Option<String> {
+        let attrs = self.attributes();
+
+        if let Ok(id) = name.parse::<AttributeId>() {
+            if let Some(v) = attrs.get_value(id) {
+                return Some(v.to_string());
+            }
+            return None;
+        }
+
+        attrs.iter().find(|a| !a.is_svg() && a.name.to_string() == name)
+            .map(|a| a.value.to_string())
+    }
+
+    fn prev_sibling_element(&self) -> Option<Node> {
+        let mut cur = self.prev_sibling();
+        while let Some(n) = cur {
+            if n.node_type() == NodeType::Element {
+                return Some(n);
+            }
+            cur = n.prev_sibling();
+        }
+        None
+    }
+}
+
+fn hash_str(s: &str) -> u64 {
+    // FNV-1a: fast, good enough distribution for a membership filter.
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in s.bytes() {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+thread_local! {
+    // Cache of the computed element-child lists, keyed by parent node, so repeated
+    // `:nth-child` tests over the same parent only walk its children once per
+    // `Document::select`/`Node::matches` call - cleared at the start of each (see
+    // `clear_nth_child_cache`), same pattern as `parser/text.rs`'s `XMLSPACE_CACHE`.
+    // Without that, the cache would grow without bound across the process's lifetime
+    // and could return stale sibling lists if a document is mutated between calls.
+    static NTH_CHILD_CACHE: RefCell<Vec<(Node, Vec<Node>)>> = RefCell::new(Vec::new());
+}
+
+fn clear_nth_child_cache() {
+    NTH_CHILD_CACHE.with(|cache| cache.borrow_mut().clear());
+}
+
+fn element_child_index(node: &Node) -> Option<usize> {
+    let parent = node.parent()?;
+
+    NTH_CHILD_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+
+        if !cache.iter().any(|&(ref p, _)| *p == parent) {
+            let children: Vec<Node> = parent.children()
+                .filter(|n| n.node_type() == NodeType::Element)
+                .collect();
+            cache.push((parent.clone(), children));
+        }
+
+        cache.iter()
+            .find(|&&(ref p, _)| *p == parent)
+            .and_then(|&(_, ref children)| children.iter().position(|n| *n == *node))
+    })
+}
+
+fn matches_nth(a: i32, b: i32, node: &Node) -> bool {
+    match element_child_index(node) {
+        Some(idx0) => {
+            // CSS indices are 1-based.
+            let index = idx0 as i32 + 1;
+
+            if a == 0 {
+                index == b
+            } else {
+                let diff = index - b;
+                diff % a == 0 && diff / a >= 0
+            }
+        }
+        None => false,
+    }
+}
+
+fn matches_compound(sel: &CompoundSelector, node: &Node) -> bool {
+    if node.node_type() != NodeType::Element {
+        return false;
+    }
+
+    if let Some(ref tag) = sel.tag_name {
+        if node.tag_name_string() != *tag {
+            return false;
+        }
+    }
+
+    if let Some(ref id) = sel.id {
+        if node.id_string().as_deref() != Some(id.as_str()) {
+            return false;
+        }
+    }
+
+    for class in &sel.classes {
+        if !node.class_list().iter().any(|c| c == class) {
+            return false;
+        }
+    }
+
+    for &(ref name, ref m) in &sel.attrs {
+        let value = node.attribute_string(name);
+        match (m, &value) {
+            (AttrMatch::Exists, Some(_)) => {}
+            (AttrMatch::Equals(ref want), Some(v)) if v == want => {}
+            (AttrMatch::Includes(ref want), Some(v)) if v.split_whitespace().any(|p| p == want) => {}
+            _ => return false,
+        }
+    }
+
+    if sel.first_child && element_child_index(node) != Some(0) {
+        return false;
+    }
+
+    if sel.last_child {
+        let is_last = node.parent()
+            .map(|p| p.children().filter(|n| n.node_type() == NodeType::Element).last())
+            .and_then(|n| n)
+            .map_or(false, |last| last == *node);
+        if !is_last {
+            return false;
+        }
+    }
+
+    if let Some(NthChild::AnB(a, b)) = sel.nth_child {
+        if !matches_nth(a, b, node) {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn matches_complex(sel: &ComplexSelector, node: &Node) -> bool {
+    if sel.steps.is_empty() {
+        return false;
+    }
+
+    if !matches_compound(&sel.steps[0].selector, node) {
+        return false;
+    }
+
+    let mut bloom = BloomFilter::new();
+    let mut ancestor_cursor = node.parent();
+    while let Some(ref a) = ancestor_cursor {
+        bloom.push_node(a);
+        ancestor_cursor = a.parent();
+    }
+
+    match_rest(&sel.steps, 1, node.clone(), &bloom)
+}
+
+fn match_rest(steps: &[Step], idx: usize, current: Node, bloom: &BloomFilter) -> bool {
+    if idx >= steps.len() {
+        return true;
+    }
+
+    let step = &steps[idx];
+    match step.combinator {
+        Some(Combinator::Child) => {
+            match current.parent() {
+                Some(p) => matches_compound(&step.selector, &p) && match_rest(steps, idx + 1, p, bloom),
+                None => false,
+            }
+        }
+        Some(Combinator::Descendant) | None => {
+            // A required ancestor feature that is provably absent lets us bail out
+            // of the whole ancestor walk early.
+            if let Some(ref tag) = step.selector.tag_name {
+                if !bloom.might_contain(tag) {
+                    return false;
+                }
+            }
+            if let Some(ref id) = step.selector.id {
+                if !bloom.might_contain(&format!("#{}", id)) {
+                    return false;
+                }
+            }
+            for class in &step.selector.classes {
+                if !bloom.might_contain(&format!(".{}", class)) {
+                    return false;
+                }
+            }
+
+            let mut ancestor = current.parent();
+            while let Some(a) = ancestor {
+                if matches_compound(&step.selector, &a) && match_rest(steps, idx + 1, a.clone(), bloom) {
+                    return true;
+                }
+                ancestor = a.parent();
+            }
+            false
+        }
+        Some(Combinator::AdjacentSibling) => {
+            match current.prev_sibling_element() {
+                Some(s) => matches_compound(&step.selector, &s) && match_rest(steps, idx + 1, s, bloom),
+                None => false,
+            }
+        }
+        Some(Combinator::GeneralSibling) => {
+            let mut sibling = current.prev_sibling_element();
+            while let Some(s) = sibling {
+                if matches_compound(&step.selector, &s) && match_rest(steps, idx + 1, s.clone(), bloom) {
+                    return true;
+                }
+                sibling = s.prev_sibling_element();
+            }
+            false
+        }
+    }
+}
+
+/// An iterator over nodes that match a `Selector`, produced by `Document::select`.
+pub struct Select<'a> {
+    selector: Selector,
+    iter: ::tree::iterator::Descendants<'a>,
+}
+
+impl<'a> Iterator for Select<'a> {
+    type Item = Node;
+
+    fn next(&mut self) -> Option<Node> {
+        for node in &mut self.iter {
+            if self.selector.matches(&node) {
+                return Some(node);
+            }
+        }
+        None
+    }
+}
+
+impl Document {
+    /// Returns an iterator over all nodes in the document matching the given CSS selector.
+    pub fn select<'a>(&'a self, selector: &str) -> Result<Select<'a>, SelectorError> {
+        let selector = Selector::parse(selector)?;
+        clear_nth_child_cache();
+        Ok(Select {
+            selector,
+            iter: self.root().descendants(),
+        })
+    }
+}
+
+impl Node {
+    /// Checks whether this node matches the given CSS selector.
+    pub fn matches(&self, selector: &str) -> Result<bool, SelectorError> {
+        let selector = Selector::parse(selector)?;
+        clear_nth_child_cache();
+        Ok(selector.matches(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Document;
+
+    #[test]
+    fn child_combinator_does_not_match_descendant() {
+        let doc = Document::from_str("<svg><div><x><p/></x></div></svg>").unwrap();
+
+        // `div > p` must not match: `p` is a grandchild of `div`, not a direct child.
+        assert!(doc.select("div > p").unwrap().next().is_none());
+        // The plain descendant combinator still matches the same tree.
+        assert!(doc.select("div p").unwrap().next().is_some());
+    }
+
+    #[test]
+    fn mixed_combinators_are_not_cascaded_across_every_step() {
+        // `a > b c` requires `b` to be a *direct* child of `a`, and `c` a descendant of
+        // `b`. Inserting an extra `x` between `a` and `b` breaks the `>` link, so this
+        // must not match - a buggy shift that cascades the last-seen combinator onto
+        // every earlier step would wrongly treat `a`'s link as `Descendant` too and
+        // match anyway.
+        let broken = Document::from_str("<svg><a><x><b><c/></b></x></a></svg>").unwrap();
+        assert!(broken.select("a > b c").unwrap().next().is_none());
+
+        // The direct version (no `x` in between) must still match.
+        let direct = Document::from_str("<svg><a><b><c/></b></a></svg>").unwrap();
+        assert!(direct.select("a > b c").unwrap().next().is_some());
+    }
+}
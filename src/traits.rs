@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::io;
 use std::str::FromStr;
 
 use svgparser::TextFrame;
@@ -50,6 +51,30 @@ pub trait WriteToString: WriteBuffer {
     }
 }
 
+/// The trait for writing data into an `io::Write` sink (a file, socket or HTTP response body).
+///
+/// The default implementation assembles the whole output in an in-memory `Vec<u8>` via
+/// `write_buf_opt` and then hands it to the sink with a single `write_all`; the output is
+/// not streamed piece by piece. `to_string_with_opt` and `write_buf`/`write_buf_opt`
+/// remain available for callers that want an owned `String`/`Vec<u8>`.
+pub trait WriteToStream: WriteBuffer {
+    /// Writes data to `dest` using specified WriteOptions.
+    fn write_to_opt<W: io::Write>(&self, opt: &WriteOptions, dest: &mut W) -> io::Result<()> {
+        // `write_buf_opt` is implemented per-type in terms of a `Vec<u8>` buffer, so the
+        // whole output is built in memory first and then written to `dest` in one go.
+        let mut buf = Vec::with_capacity(4096);
+        self.write_buf_opt(opt, &mut buf);
+        dest.write_all(&buf)
+    }
+
+    /// Writes data to `dest` using default WriteOptions.
+    fn write_to<W: io::Write>(&self, dest: &mut W) -> io::Result<()> {
+        self.write_to_opt(&WriteOptions::default(), dest)
+    }
+}
+
+impl<T: WriteBuffer> WriteToStream for T {}
+
 macro_rules! impl_display {
     ($t:ty) => (
         impl fmt::Display for $t {
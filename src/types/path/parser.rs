@@ -106,3 +106,296 @@ impl ParseFromSpan for Path {
         Ok(p)
     }
 }
+
+impl Path {
+    /// Returns a copy of this path with every segment rewritten into an absolute
+    /// `MoveTo`, `LineTo`, `CurveTo` or `ClosePath` - no relative coordinates, no
+    /// `H`/`V`/`S`/`T`/`A` shorthand.
+    ///
+    /// This mirrors what `usvgr`'s simplification layer does before handing a path off
+    /// to a renderer that only wants to deal with one segment shape.
+    pub fn normalize(&self) -> Path {
+        let mut out = Path::new();
+
+        // Running current point, the start of the current subpath (for `ClosePath`),
+        // and the last control point of a `CurveTo`/`Quadratic` (for the `S`/`T` reflection).
+        let (mut cx, mut cy) = (0.0, 0.0);
+        let (mut sx, mut sy) = (0.0, 0.0);
+        let mut last_cubic_ctrl: Option<(f64, f64)> = None;
+        let mut last_quad_ctrl: Option<(f64, f64)> = None;
+
+        for seg in self.iter().cloned() {
+            let abs = seg.absolute;
+
+            macro_rules! abs_xy {
+                ($x:expr, $y:expr) => {
+                    if abs { ($x, $y) } else { (cx + $x, cy + $y) }
+                }
+            }
+
+            let mut is_cubic = false;
+            let mut is_quad = false;
+
+            match seg.data {
+                SegmentData::MoveTo { x, y } => {
+                    let (x, y) = abs_xy!(x, y);
+                    out.push(Segment { absolute: true, data: SegmentData::MoveTo { x, y } });
+                    cx = x; cy = y;
+                    sx = x; sy = y;
+                }
+                SegmentData::LineTo { x, y } => {
+                    let (x, y) = abs_xy!(x, y);
+                    out.push(Segment { absolute: true, data: SegmentData::LineTo { x, y } });
+                    cx = x; cy = y;
+                }
+                SegmentData::HorizontalLineTo { x } => {
+                    let x = if abs { x } else { cx + x };
+                    out.push(Segment { absolute: true, data: SegmentData::LineTo { x, y: cy } });
+                    cx = x;
+                }
+                SegmentData::VerticalLineTo { y } => {
+                    let y = if abs { y } else { cy + y };
+                    out.push(Segment { absolute: true, data: SegmentData::LineTo { x: cx, y } });
+                    cy = y;
+                }
+                SegmentData::CurveTo { x1, y1, x2, y2, x, y } => {
+                    let (x1, y1) = abs_xy!(x1, y1);
+                    let (x2, y2) = abs_xy!(x2, y2);
+                    let (x, y) = abs_xy!(x, y);
+                    out.push(Segment { absolute: true, data: SegmentData::CurveTo { x1, y1, x2, y2, x, y } });
+                    cx = x; cy = y;
+                    last_cubic_ctrl = Some((x2, y2));
+                    is_cubic = true;
+                }
+                SegmentData::SmoothCurveTo { x2, y2, x, y } => {
+                    let (x2, y2) = abs_xy!(x2, y2);
+                    let (x, y) = abs_xy!(x, y);
+                    // Reflect the previous cubic's second control point about the current point.
+                    let (x1, y1) = match last_cubic_ctrl {
+                        Some((px, py)) => (2.0 * cx - px, 2.0 * cy - py),
+                        None => (cx, cy),
+                    };
+                    out.push(Segment { absolute: true, data: SegmentData::CurveTo { x1, y1, x2, y2, x, y } });
+                    cx = x; cy = y;
+                    last_cubic_ctrl = Some((x2, y2));
+                    is_cubic = true;
+                }
+                SegmentData::Quadratic { x1, y1, x, y } => {
+                    let (x1, y1) = abs_xy!(x1, y1);
+                    let (x, y) = abs_xy!(x, y);
+                    let (cx1, cy1, cx2, cy2) = quad_to_cubic(cx, cy, x1, y1, x, y);
+                    out.push(Segment {
+                        absolute: true,
+                        data: SegmentData::CurveTo { x1: cx1, y1: cy1, x2: cx2, y2: cy2, x, y },
+                    });
+                    cx = x; cy = y;
+                    last_quad_ctrl = Some((x1, y1));
+                    is_quad = true;
+                }
+                SegmentData::SmoothQuadratic { x, y } => {
+                    let (x, y) = abs_xy!(x, y);
+                    let (x1, y1) = match last_quad_ctrl {
+                        Some((px, py)) => (2.0 * cx - px, 2.0 * cy - py),
+                        None => (cx, cy),
+                    };
+                    let (cx1, cy1, cx2, cy2) = quad_to_cubic(cx, cy, x1, y1, x, y);
+                    out.push(Segment {
+                        absolute: true,
+                        data: SegmentData::CurveTo { x1: cx1, y1: cy1, x2: cx2, y2: cy2, x, y },
+                    });
+                    cx = x; cy = y;
+                    last_quad_ctrl = Some((x1, y1));
+                    is_quad = true;
+                }
+                SegmentData::EllipticalArc { rx, ry, x_axis_rotation, large_arc, sweep, x, y } => {
+                    let (x, y) = abs_xy!(x, y);
+                    arc_to_cubics(cx, cy, rx, ry, x_axis_rotation, large_arc, sweep, x, y, &mut out);
+                    cx = x; cy = y;
+                }
+                SegmentData::ClosePath => {
+                    out.push(Segment { absolute: true, data: SegmentData::ClosePath });
+                    cx = sx; cy = sy;
+                }
+            }
+
+            if !is_cubic { last_cubic_ctrl = None; }
+            if !is_quad { last_quad_ctrl = None; }
+        }
+
+        out
+    }
+}
+
+// Converts a quadratic Bezier control point into the two cubic control points that
+// produce the same curve (elevation of degree 2 to degree 3).
+fn quad_to_cubic(x0: f64, y0: f64, x1: f64, y1: f64, x: f64, y: f64) -> (f64, f64, f64, f64) {
+    let cx1 = x0 + 2.0 / 3.0 * (x1 - x0);
+    let cy1 = y0 + 2.0 / 3.0 * (y1 - y0);
+    let cx2 = x + 2.0 / 3.0 * (x1 - x);
+    let cy2 = y + 2.0 / 3.0 * (y1 - y);
+    (cx1, cy1, cx2, cy2)
+}
+
+// Converts an SVG elliptical arc segment into one or more cubic Bezier `CurveTo` segments,
+// following the endpoint-to-center parametrization from the SVG spec (F.6.5 / F.6.6).
+#[allow(clippy::too_many_arguments)]
+fn arc_to_cubics(
+    x0: f64, y0: f64,
+    mut rx: f64, mut ry: f64,
+    x_axis_rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+    x: f64, y: f64,
+    out: &mut Path,
+) {
+    // Degenerate cases degrade to a straight line.
+    if (x0 - x).abs() < 1e-9 && (y0 - y).abs() < 1e-9 {
+        out.push(Segment { absolute: true, data: SegmentData::LineTo { x, y } });
+        return;
+    }
+    if rx.abs() < 1e-9 || ry.abs() < 1e-9 {
+        out.push(Segment { absolute: true, data: SegmentData::LineTo { x, y } });
+        return;
+    }
+
+    rx = rx.abs();
+    ry = ry.abs();
+    let phi = x_axis_rotation.to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+
+    // Step 1: compute (x1', y1') - the midpoint in the rotated, translated frame.
+    let dx2 = (x0 - x) / 2.0;
+    let dy2 = (y0 - y) / 2.0;
+    let x1p = cos_phi * dx2 + sin_phi * dy2;
+    let y1p = -sin_phi * dx2 + cos_phi * dy2;
+
+    // Step 2: correct out-of-range radii.
+    let lambda = (x1p * x1p) / (rx * rx) + (y1p * y1p) / (ry * ry);
+    if lambda > 1.0 {
+        let s = lambda.sqrt();
+        rx *= s;
+        ry *= s;
+    }
+
+    // Step 3: compute (cx', cy') - the center in the rotated frame.
+    let rx_sq = rx * rx;
+    let ry_sq = ry * ry;
+    let x1p_sq = x1p * x1p;
+    let y1p_sq = y1p * y1p;
+
+    let num = (rx_sq * ry_sq - rx_sq * y1p_sq - ry_sq * x1p_sq).max(0.0);
+    let den = rx_sq * y1p_sq + ry_sq * x1p_sq;
+    let mut coef = (num / den).sqrt();
+    if large_arc == sweep {
+        coef = -coef;
+    }
+
+    let cxp = coef * (rx * y1p / ry);
+    let cyp = coef * -(ry * x1p / rx);
+
+    // Step 4: transform back to get the real center.
+    let cx = cos_phi * cxp - sin_phi * cyp + (x0 + x) / 2.0;
+    let cy = sin_phi * cxp + cos_phi * cyp + (y0 + y) / 2.0;
+
+    let angle = |ux: f64, uy: f64, vx: f64, vy: f64| -> f64 {
+        let dot = ux * vx + uy * vy;
+        let len = ((ux * ux + uy * uy) * (vx * vx + vy * vy)).sqrt();
+        let mut a = (dot / len).max(-1.0).min(1.0).acos();
+        if ux * vy - uy * vx < 0.0 {
+            a = -a;
+        }
+        a
+    };
+
+    let theta1 = angle(1.0, 0.0, (x1p - cxp) / rx, (y1p - cyp) / ry);
+    let mut dtheta = angle(
+        (x1p - cxp) / rx, (y1p - cyp) / ry,
+        (-x1p - cxp) / rx, (-y1p - cyp) / ry,
+    );
+
+    if !sweep && dtheta > 0.0 {
+        dtheta -= 2.0 * ::std::f64::consts::PI;
+    } else if sweep && dtheta < 0.0 {
+        dtheta += 2.0 * ::std::f64::consts::PI;
+    }
+
+    // Step 5: split into segments no wider than 90 degrees and approximate each with a cubic.
+    let segments = (dtheta.abs() / (::std::f64::consts::FRAC_PI_2)).ceil().max(1.0) as u32;
+    let delta = dtheta / segments as f64;
+    let k = 4.0 / 3.0 * (delta / 4.0).tan();
+
+    let mut theta = theta1;
+    for _ in 0..segments {
+        let theta_end = theta + delta;
+
+        let (sin1, cos1) = theta.sin_cos();
+        let (sin2, cos2) = theta_end.sin_cos();
+
+        // Points and tangents on the unrotated, centered ellipse.
+        let p1 = (rx * cos1, ry * sin1);
+        let p2 = (rx * cos2, ry * sin2);
+        let t1 = (-rx * sin1, ry * cos1);
+        let t2 = (-rx * sin2, ry * cos2);
+
+        let c1 = (p1.0 + k * t1.0, p1.1 + k * t1.1);
+        let c2 = (p2.0 - k * t2.0, p2.1 - k * t2.1);
+
+        let rotate = |px: f64, py: f64| -> (f64, f64) {
+            (cx + cos_phi * px - sin_phi * py, cy + sin_phi * px + cos_phi * py)
+        };
+
+        let (x1, y1) = rotate(c1.0, c1.1);
+        let (x2, y2) = rotate(c2.0, c2.1);
+        let (ex, ey) = rotate(p2.0, p2.1);
+
+        out.push(Segment {
+            absolute: true,
+            data: SegmentData::CurveTo { x1, y1, x2, y2, x: ex, y: ey },
+        });
+
+        theta = theta_end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{quad_to_cubic, arc_to_cubics};
+    use super::super::{Path, Segment, SegmentData};
+
+    #[test]
+    fn normalize_resolves_relative_segments_to_absolute() {
+        let mut p = Path::new();
+        p.push(Segment { absolute: true, data: SegmentData::MoveTo { x: 10.0, y: 10.0 } });
+        p.push(Segment { absolute: false, data: SegmentData::LineTo { x: 5.0, y: 5.0 } });
+        p.push(Segment { absolute: false, data: SegmentData::ClosePath });
+
+        let out = p.normalize();
+        let segs: Vec<Segment> = out.iter().cloned().collect();
+
+        assert_eq!(segs[0].data, SegmentData::MoveTo { x: 10.0, y: 10.0 });
+        // The relative LineTo(5, 5) must land at (10 + 5, 10 + 5), not (5, 5).
+        assert_eq!(segs[1].data, SegmentData::LineTo { x: 15.0, y: 15.0 });
+        assert!(segs[1].absolute);
+    }
+
+    #[test]
+    fn quad_to_cubic_elevates_degree_correctly() {
+        // A quadratic from (0, 0) via control (10, 0) to (10, 10) should elevate to the
+        // standard 2/3-of-the-way cubic control points.
+        let (cx1, cy1, cx2, cy2) = quad_to_cubic(0.0, 0.0, 10.0, 0.0, 10.0, 10.0);
+        assert!((cx1 - 6.666_666_666_666_667).abs() < 1e-9);
+        assert!((cy1 - 0.0).abs() < 1e-9);
+        assert!((cx2 - 10.0).abs() < 1e-9);
+        assert!((cy2 - 3.333_333_333_333_333).abs() < 1e-9);
+    }
+
+    #[test]
+    fn arc_to_cubics_degrades_to_a_line_when_a_radius_is_zero() {
+        let mut out = Path::new();
+        arc_to_cubics(0.0, 0.0, 0.0, 5.0, 0.0, false, false, 10.0, 0.0, &mut out);
+
+        let segs: Vec<Segment> = out.iter().cloned().collect();
+        assert_eq!(segs.len(), 1);
+        assert_eq!(segs[0].data, SegmentData::LineTo { x: 10.0, y: 0.0 });
+    }
+}
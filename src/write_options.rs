@@ -130,6 +130,27 @@ pub struct WriteOptions {
     ///
     /// Default: disabled
     pub simplify_transform_matrices: bool,
+
+    /// Write the output as gzip-compressed SVG (`.svgz`).
+    ///
+    /// Honored by `Document::write_to`/`write_to_opt` (and, unconditionally, by
+    /// `write_gz`/`to_vec_gz`). Has no effect on `to_string`/`write_buf`, which return
+    /// `String`/append to an existing `Vec<u8>` and so always produce plain text.
+    ///
+    /// Default: disabled
+    pub compress: bool,
+
+    /// The number of digits to keep after the decimal point when writing a plain `number`
+    /// attribute value (`AttributeValue::Number`), via `write_num`.
+    ///
+    /// Trailing zeros and a now-redundant decimal point are stripped afterwards, so
+    /// `10.000000` becomes `10` and, at precision 3, `0.333333` becomes `0.333`.
+    ///
+    /// `path`/`points`/`transform`/list-valued numbers are written through `svgtypes`'s
+    /// own formatting and are not affected by this option.
+    ///
+    /// Default: 8
+    pub numbers_precision: u8,
 }
 
 impl Default for WriteOptions {
@@ -146,6 +167,34 @@ impl Default for WriteOptions {
                 remove_duplicated_commands: false,
             },
             simplify_transform_matrices: false,
+            compress: false,
+            numbers_precision: 8,
         }
     }
 }
+
+/// Rounds `value` to `precision` decimal digits and writes it to `buf`, stripping
+/// trailing zeros and a now-redundant decimal point.
+///
+/// This mirrors how CSS value serialization rounds numbers before emitting them. Used by
+/// `AttributeValue::Number`'s `WriteBuffer` impl together with `numbers_precision`.
+pub fn write_num(value: f64, precision: u8, buf: &mut Vec<u8>) {
+    use std::io::Write;
+
+    let precision = precision as usize;
+    let rounded = format!("{:.*}", precision, value);
+
+    let trimmed = if rounded.contains('.') {
+        let t = rounded.trim_end_matches('0');
+        t.trim_end_matches('.')
+    } else {
+        rounded.as_str()
+    };
+
+    // `-0` isn't a useful distinct value - normalize it away like `0`.
+    if trimmed == "-0" {
+        buf.push(b'0');
+    } else {
+        write!(buf, "{}", trimmed).unwrap();
+    }
+}